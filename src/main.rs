@@ -39,8 +39,11 @@ fn main() -> eframe::Result<()> {
 // The messages that can be passed between the
 // main egui thread and the tokio thread
 pub enum AppMessage {
-    ApplicationLoad(Vec<String>),
-    ItemAdded(String),
+    ApplicationLoad(Vec<(i64, String)>),
+    ItemAdded(i64, String),
+    ItemUpdated { id: i64, name: String },
+    ItemDeleted { id: i64 },
+    Error(String),
 }
 
 // The state of the application
@@ -48,8 +51,11 @@ pub enum AppMessage {
 struct App {
     pub tx: Sender<AppMessage>,
     pub rx: Receiver<AppMessage>,
-    pub items: Vec<String>,
+    pub db: DbSender,
+    pub tasks: mpsc::Sender<Task>,
+    pub items: Vec<(i64, String)>,
     pub new_item_name: String,
+    pub last_error: Option<String>,
 }
 
 impl Default for App {
@@ -57,20 +63,26 @@ impl Default for App {
         let (tx, rx) = std::sync::mpsc::channel();
 
         App {
+            // Loading, renaming and deleting items go through a single
+            // executor task; here we only keep the channel to talk to it.
+            db: spawn_db_executor(URL),
+            // Durable, retryable background work is enqueued onto the worker
+            // pool instead.
+            tasks: spawn_worker_pool(URL, tx.clone()),
             tx,
             rx,
             items: vec![],
             new_item_name: "".to_string(),
+            last_error: None,
         }
     }
 }
 impl App {
     fn new() -> Self {
         let app: Self = Default::default();
-        let tx = app.tx.clone();
 
         // Initialize the database upon app creation
-        init_database(tx);
+        init_database(app.db.clone(), app.tasks.clone(), app.tx.clone());
 
         app
     }
@@ -83,11 +95,29 @@ impl eframe::App for App {
             Ok(AppMessage::ApplicationLoad(items)) => {
                 println!("Application loaded items: {:?}", items);
                 self.items = items;
+                self.last_error = None;
             }
-            Ok(AppMessage::ItemAdded(item)) => {
-                println!("Item added: {:?}", item);
-                self.items.insert(0, item);
+            Ok(AppMessage::ItemAdded(id, name)) => {
+                println!("Item added: {:?}", name);
+                self.items.insert(0, (id, name));
                 self.new_item_name = String::new();
+                self.last_error = None;
+            }
+            Ok(AppMessage::ItemUpdated { id, name }) => {
+                println!("Item updated: {:?}", name);
+                if let Some(item) = self.items.iter_mut().find(|(item_id, _)| *item_id == id) {
+                    item.1 = name;
+                }
+                self.last_error = None;
+            }
+            Ok(AppMessage::ItemDeleted { id }) => {
+                println!("Item deleted: {:?}", id);
+                self.items.retain(|(item_id, _)| *item_id != id);
+                self.last_error = None;
+            }
+            Ok(AppMessage::Error(error)) => {
+                eprintln!("Database error: {}", error);
+                self.last_error = Some(error);
             }
             _ => {}
         }
@@ -99,92 +129,546 @@ impl eframe::App for App {
 
                 // Handle click
                 if ui.add(button).clicked() && !self.new_item_name.is_empty() {
-                    add_item(self.new_item_name.to_string(), self.tx.clone());
+                    add_item(self.new_item_name.to_string(), self.tasks.clone());
                 }
 
                 // Handle enter
                 if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-                    add_item(self.new_item_name.to_string(), self.tx.clone());
+                    add_item(self.new_item_name.to_string(), self.tasks.clone());
                 }
             });
 
-            for a in &self.items {
-                ui.label(a);
+            // Each row is an inline editable field plus a delete button;
+            // collect the actions first so we aren't dispatching while the
+            // `items` borrow is still live.
+            let mut edits: Vec<(i64, String)> = Vec::new();
+            let mut deletes: Vec<i64> = Vec::new();
+
+            for (id, name) in self.items.iter_mut() {
+                ui.horizontal(|ui| {
+                    let input = ui.text_edit_singleline(name);
+                    if input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        edits.push((*id, name.clone()));
+                    }
+                    if ui.button("Delete").clicked() {
+                        deletes.push(*id);
+                    }
+                });
+            }
+
+            for (id, name) in edits {
+                update_item(id, name, self.db.clone(), self.tx.clone());
+            }
+            for id in deletes {
+                delete_item(id, self.db.clone(), self.tx.clone());
             }
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            // Show the most recent database error as a dismissible banner so
+            // transient sqlx failures are recoverable rather than fatal.
+            if let Some(error) = self.last_error.clone() {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::RED, &error);
+                    if ui.button("Dismiss").clicked() {
+                        self.last_error = None;
+                    }
+                });
+            }
+
             ui.label("Hello World");
         });
     }
 }
 
 use async_once_cell::OnceCell;
-use sqlx::{migrate::MigrateDatabase, sqlite::SqlitePoolOptions, Executor, Pool, Row, Sqlite};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    Pool, Row, Sqlite,
+};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 static POOL: OnceCell<Pool<Sqlite>> = OnceCell::new();
 static URL: &str = "sqlite://items.db";
 
+// Handle the egui side uses to talk to the DB executor task.
+type DbSender = mpsc::Sender<DbCommand>;
+
+// Work items sent to the DB executor. Each carries a oneshot channel the
+// executor replies on once the query has run, so callers never touch the
+// pool directly.
+enum DbCommand {
+    UpdateItem {
+        id: i64,
+        name: String,
+        reply: oneshot::Sender<Result<(), sqlx::Error>>,
+    },
+    DeleteItem {
+        id: i64,
+        reply: oneshot::Sender<Result<(), sqlx::Error>>,
+    },
+    LoadAll {
+        reply: oneshot::Sender<Result<Vec<(i64, String)>, sqlx::Error>>,
+    },
+    LoadTasks {
+        reply: oneshot::Sender<Result<Vec<Task>, sqlx::Error>>,
+    },
+}
+
+// Spawn the single task that runs the queries sent as `DbCommand`s, one at a
+// time. The worker pool shares the same connection pool and runs its own
+// queries (item inserts and `tasks` bookkeeping) outside this executor.
+fn spawn_db_executor(url: &'static str) -> DbSender {
+    let (tx, mut rx) = mpsc::channel::<DbCommand>(32);
+
+    tokio::spawn(async move {
+        let pool = get_pool(url).await;
+
+        if let Err(e) = MIGRATOR.run(pool).await {
+            eprintln!("Could not run migrations: {e}");
+            return;
+        }
+
+        while let Some(command) = rx.recv().await {
+            match command {
+                DbCommand::UpdateItem { id, name, reply } => {
+                    let _ = reply.send(update_item_name(pool, id, &name).await);
+                }
+                DbCommand::DeleteItem { id, reply } => {
+                    let _ = reply.send(delete_item_row(pool, id).await);
+                }
+                DbCommand::LoadAll { reply } => {
+                    let _ = reply.send(load_items(pool).await);
+                }
+                DbCommand::LoadTasks { reply } => {
+                    let _ = reply.send(load_tasks(pool).await);
+                }
+            }
+        }
+    });
+
+    tx
+}
+
+// Versioned schema lives in `migrations/`; `migrate!()` embeds those files
+// at compile time so the demo can grow columns/tables without editing inline
+// DDL string literals.
+static MIGRATOR: Migrator = sqlx::migrate!();
+
 async fn get_pool<'a>(url: &str) -> &'a Pool<Sqlite> {
     POOL.get_or_init(async {
+        // WAL lets readers run alongside a writer and `busy_timeout` makes
+        // writers retry instead of immediately failing with "database is
+        // locked" when the UI fires off concurrent tasks. `create_if_missing`
+        // replaces the old `database_exists`/`create_database` dance.
+        let options = SqliteConnectOptions::from_str(url)
+            .expect("Invalid database URL")
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(Duration::from_secs(5));
+
         SqlitePoolOptions::new()
             .max_connections(5)
-            .connect(url)
+            .connect_with(options)
             .await
             .expect("Could not create DB Pool")
     })
     .await
 }
 
-// Spawning a tokio thread and initialize the database
-fn init_database(tx: Sender<AppMessage>) {
+// Low-level query helpers, parameterised over the pool rather than reaching for
+// the `POOL` global so they can be exercised against an in-memory database in
+// the tests below.
+async fn insert_item(pool: &Pool<Sqlite>, name: &str) -> Result<i64, sqlx::Error> {
+    sqlx::query("INSERT INTO item (name) VALUES (?)")
+        .bind(name)
+        .execute(pool)
+        .await
+        .map(|done| done.last_insert_rowid())
+}
+
+async fn update_item_name(pool: &Pool<Sqlite>, id: i64, name: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE item SET name = ? WHERE id = ?")
+        .bind(name)
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+}
+
+async fn delete_item_row(pool: &Pool<Sqlite>, id: i64) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM item WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map(|_| ())
+}
+
+async fn load_items(pool: &Pool<Sqlite>) -> Result<Vec<(i64, String)>, sqlx::Error> {
+    let rows = sqlx::query("SELECT id, name FROM item")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<i64, usize>(0),
+                String::from(row.get::<&str, usize>(1)),
+            )
+        })
+        .collect())
+}
+
+async fn load_tasks(pool: &Pool<Sqlite>) -> Result<Vec<Task>, sqlx::Error> {
+    let rows = sqlx::query("SELECT id, kind, payload, attempts FROM tasks")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| Task {
+            id: Some(row.get::<i64, usize>(0)),
+            kind: row.get::<&str, usize>(1).to_string(),
+            payload: row.get::<Vec<u8>, usize>(2),
+            attempts: row.get::<i64, usize>(3) as u32,
+        })
+        .collect())
+}
+
+// Ask the executor for every item and forward the result to the egui thread.
+// Any unfinished rows in the `tasks` table are re-enqueued so durable
+// background work survives a restart.
+fn init_database(db: DbSender, tasks: mpsc::Sender<Task>, tx: Sender<AppMessage>) {
     tokio::spawn(async move {
-        if !Sqlite::database_exists(URL).await.unwrap_or(false) {
-            Sqlite::create_database(URL)
-                .await
-                .expect("Could not create DB");
+        let (reply, rx) = oneshot::channel();
+        if db.send(DbCommand::LoadAll { reply }).await.is_err() {
+            return;
         }
 
-        let pool = get_pool(URL).await;
+        match rx.await {
+            Ok(Ok(items)) => {
+                let _ = tx.send(AppMessage::ApplicationLoad(items));
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(AppMessage::Error(e.to_string()));
+            }
+            Err(_) => {}
+        }
 
-        pool.execute(
-            r#"
-      CREATE TABLE IF NOT EXISTS item (
-        id INTEGER PRIMARY KEY,
-        name TEXT NOT NULL
-      );        
-    "#,
-        )
-        .await
-        .expect("Could not create DB Schema");
+        let (reply, rx) = oneshot::channel();
+        if db.send(DbCommand::LoadTasks { reply }).await.is_err() {
+            return;
+        }
 
-        let items: Vec<String> = sqlx::query("SELECT name FROM item")
-            .fetch_all(pool)
+        match rx.await {
+            Ok(Ok(pending)) => {
+                for task in pending {
+                    let _ = tasks.send(task).await;
+                }
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(AppMessage::Error(e.to_string()));
+            }
+            Err(_) => {}
+        }
+    });
+}
+
+// Enqueue a durable, retryable "add_item" task onto the worker pool. The
+// handler registered for that kind performs the insert and reports the new
+// row back to the egui thread.
+fn add_item(name: String, tasks: mpsc::Sender<Task>) {
+    tokio::spawn(async move {
+        let _ = tasks
+            .send(Task {
+                id: None,
+                kind: "add_item".to_string(),
+                payload: name.into_bytes(),
+                attempts: 0,
+            })
+            .await;
+    });
+}
+
+// Ask the executor to rename an item and forward the result to the egui thread.
+fn update_item(id: i64, name: String, db: DbSender, tx: Sender<AppMessage>) {
+    tokio::spawn(async move {
+        let (reply, rx) = oneshot::channel();
+        if db
+            .send(DbCommand::UpdateItem {
+                id,
+                name: name.clone(),
+                reply,
+            })
             .await
-            .expect("Could not query item table")
-            .iter()
-            .map(|row| String::from(row.get::<&str, usize>(0)))
-            .collect();
+            .is_err()
+        {
+            return;
+        }
 
-        let _ = tx.send(AppMessage::ApplicationLoad(items));
+        match rx.await {
+            Ok(Ok(())) => {
+                let _ = tx.send(AppMessage::ItemUpdated { id, name });
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(AppMessage::Error(e.to_string()));
+            }
+            Err(_) => {}
+        }
     });
 }
 
-// Spawning a tokio thread and add an item to the database
-fn add_item(name: String, tx: Sender<AppMessage>) {
+// Ask the executor to delete an item and forward the result to the egui thread.
+fn delete_item(id: i64, db: DbSender, tx: Sender<AppMessage>) {
     tokio::spawn(async move {
-        let pool = get_pool(URL).await;
-
-        let item = sqlx::query(
-            r#"
-INSERT INTO item (name) VALUES(?);        
-"#,
-        )
-        .bind(&name)
-        .execute(pool)
-        .await;
+        let (reply, rx) = oneshot::channel();
+        if db.send(DbCommand::DeleteItem { id, reply }).await.is_err() {
+            return;
+        }
 
-        if item.is_ok() {
-            let _ = tx.send(AppMessage::ItemAdded(name.to_string()));
+        match rx.await {
+            Ok(Ok(())) => {
+                let _ = tx.send(AppMessage::ItemDeleted { id });
+            }
+            Ok(Err(e)) => {
+                let _ = tx.send(AppMessage::Error(e.to_string()));
+            }
+            Err(_) => {}
+        }
+    });
+}
+
+// A task handler: given the serialized payload and a pool clone, run the work
+// and report success or a sqlx error (which triggers a retry).
+type ExecuteTaskFn = Arc<
+    dyn Fn(Vec<u8>, Pool<Sqlite>) -> Pin<Box<dyn Future<Output = Result<(), sqlx::Error>> + Send>>
+        + Send
+        + Sync,
+>;
+
+// A unit of background work. `id` is the backing `tasks` row so a worker can
+// update/remove it; `attempts` counts how many times it has already failed.
+#[derive(Clone)]
+struct Task {
+    id: Option<i64>,
+    kind: String,
+    payload: Vec<u8>,
+    attempts: u32,
+}
+
+// A small durable, retryable background worker subsystem. Handlers are
+// registered by task kind; enqueued work is persisted to the `tasks` table so
+// it survives a restart, and failed work is retried up to `max_attempts`
+// before being dropped with an `AppMessage::Error`.
+struct WorkerPool {
+    handlers: BTreeMap<String, ExecuteTaskFn>,
+    num_workers: usize,
+    max_attempts: u32,
+}
+
+impl WorkerPool {
+    fn new(num_workers: usize, max_attempts: u32) -> Self {
+        WorkerPool {
+            handlers: BTreeMap::new(),
+            num_workers,
+            max_attempts,
+        }
+    }
+
+    // Register a handler for a task kind. Returns `&mut self` so registrations
+    // can be chained before the pool is started.
+    fn register(&mut self, kind: &str, handler: ExecuteTaskFn) -> &mut Self {
+        self.handlers.insert(kind.to_string(), handler);
+        self
+    }
+
+    // Spawn the worker tasks. They share a single queue receiver and pull work
+    // as they become free; `queue` is kept for re-enqueuing failed tasks.
+    fn start(
+        self,
+        pool: Pool<Sqlite>,
+        app_tx: Sender<AppMessage>,
+        queue: mpsc::Sender<Task>,
+        rx: mpsc::Receiver<Task>,
+    ) {
+        let rx = Arc::new(tokio::sync::Mutex::new(rx));
+        let handlers = Arc::new(self.handlers);
+        let max_attempts = self.max_attempts;
+
+        for _ in 0..self.num_workers {
+            let rx = rx.clone();
+            let queue = queue.clone();
+            let handlers = handlers.clone();
+            let pool = pool.clone();
+            let app_tx = app_tx.clone();
+
+            tokio::spawn(async move {
+                loop {
+                    let task = {
+                        let mut rx = rx.lock().await;
+                        match rx.recv().await {
+                            Some(task) => task,
+                            None => break,
+                        }
+                    };
+
+                    // Freshly enqueued work arrives without a row id; persist
+                    // it before running so it survives a crash mid-flight.
+                    let task = if task.id.is_none() {
+                        persist_task(&pool, task).await
+                    } else {
+                        task
+                    };
+
+                    let Some(handler) = handlers.get(&task.kind).cloned() else {
+                        let _ = app_tx.send(AppMessage::Error(format!(
+                            "No handler registered for task kind '{}'",
+                            task.kind
+                        )));
+                        remove_task(&pool, &task).await;
+                        continue;
+                    };
+
+                    match handler(task.payload.clone(), pool.clone()).await {
+                        Ok(()) => remove_task(&pool, &task).await,
+                        Err(e) => {
+                            let attempts = task.attempts + 1;
+                            if attempts < max_attempts {
+                                let retried = Task {
+                                    attempts,
+                                    ..task.clone()
+                                };
+                                update_task_attempts(&pool, &retried).await;
+                                let _ = queue.send(retried).await;
+                            } else {
+                                let _ = app_tx.send(AppMessage::Error(format!(
+                                    "Task '{}' failed after {} attempts: {}",
+                                    task.kind, attempts, e
+                                )));
+                                remove_task(&pool, &task).await;
+                            }
+                        }
+                    }
+                }
+            });
         }
+    }
+}
+
+// Insert a freshly enqueued task into the `tasks` table and stamp it with the
+// new row id so later updates/removals can find it.
+async fn persist_task(pool: &Pool<Sqlite>, task: Task) -> Task {
+    let id = sqlx::query("INSERT INTO tasks (kind, payload, attempts) VALUES (?, ?, ?)")
+        .bind(&task.kind)
+        .bind(&task.payload)
+        .bind(task.attempts)
+        .execute(pool)
+        .await
+        .map(|done| done.last_insert_rowid())
+        .ok();
+
+    Task { id, ..task }
+}
+
+// Remove a task's backing row once it has either succeeded or exhausted its
+// retries.
+async fn remove_task(pool: &Pool<Sqlite>, task: &Task) {
+    if let Some(id) = task.id {
+        let _ = sqlx::query("DELETE FROM tasks WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await;
+    }
+}
+
+// Persist the incremented attempt count before a task is retried.
+async fn update_task_attempts(pool: &Pool<Sqlite>, task: &Task) {
+    if let Some(id) = task.id {
+        let _ = sqlx::query("UPDATE tasks SET attempts = ? WHERE id = ?")
+            .bind(task.attempts)
+            .bind(id)
+            .execute(pool)
+            .await;
+    }
+}
+
+// Set up the worker pool: register the built-in handlers, start the workers and
+// return the queue sender the app uses to enqueue background work.
+fn spawn_worker_pool(url: &'static str, app_tx: Sender<AppMessage>) -> mpsc::Sender<Task> {
+    let (queue_tx, queue_rx) = mpsc::channel::<Task>(128);
+    let handle = queue_tx.clone();
+
+    tokio::spawn(async move {
+        let pool = get_pool(url).await.clone();
+
+        let mut pool_builder = WorkerPool::new(2, 3);
+        let handler_tx = app_tx.clone();
+        pool_builder.register(
+            "add_item",
+            Arc::new(move |payload, pool| {
+                let handler_tx = handler_tx.clone();
+                Box::pin(async move {
+                    let name = String::from_utf8_lossy(&payload).to_string();
+                    let id = insert_item(&pool, &name).await?;
+                    let _ = handler_tx.send(AppMessage::ItemAdded(id, name));
+                    Ok(())
+                })
+            }),
+        );
+
+        pool_builder.start(pool, app_tx, queue_tx, queue_rx);
     });
+
+    handle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A migrated in-memory database. `max_connections(1)` keeps every query on
+    // the same connection so the `sqlite::memory:` db is shared for the test.
+    async fn test_pool() -> Pool<Sqlite> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("could not open in-memory database");
+        MIGRATOR
+            .run(&pool)
+            .await
+            .expect("could not run migrations");
+        pool
+    }
+
+    #[tokio::test]
+    async fn insert_and_load_round_trips() {
+        let pool = test_pool().await;
+        let id = insert_item(&pool, "buy milk").await.unwrap();
+        let items = load_items(&pool).await.unwrap();
+        assert_eq!(items, vec![(id, "buy milk".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn update_changes_the_name() {
+        let pool = test_pool().await;
+        let id = insert_item(&pool, "draft").await.unwrap();
+        update_item_name(&pool, id, "final").await.unwrap();
+        let items = load_items(&pool).await.unwrap();
+        assert_eq!(items, vec![(id, "final".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn delete_removes_the_row() {
+        let pool = test_pool().await;
+        let id = insert_item(&pool, "temp").await.unwrap();
+        delete_item_row(&pool, id).await.unwrap();
+        assert!(load_items(&pool).await.unwrap().is_empty());
+    }
 }